@@ -66,36 +66,90 @@
 //! insert, pop+remove+insert+push
 //! ```
 //!
-//! When a test fails, the enclosing sections will be printed to stderr. Here's
-//! what happens if we comment out `v.push(one);` in the example above:
+//! When a combination fails, the enclosing sections are printed to stderr
+//! once every combination has finished running. Here's what happens if we
+//! comment out `v.push(one);` in the example above:
 //!
 //! ```text
 //! running 1 test
-//! thread 'example_test' panicked at 'assertion failed: `(left == right)`
+//! ---- 2 section combination(s) failed ----
+//! combination 0:
+//!   0) "push" at src/main.rs:26
+//!   1) "pop+remove+insert+push" at src/main.rs:17
+//! panicked at src/main.rs:22:9:
+//! assertion failed: `(left == right)`
 //!   left: `[3, 2]`,
-//!  right: `[3, 2, 1]`', src/main.rs:30:9
-//! note: Run with `RUST_BACKTRACE=1` for a backtrace.
-//! ---- the failure was inside these sections ----
-//!   0) "push" at src/main.rs:34
-//!   1) "pop+remove+insert+push" at src/main.rs:25
+//!  right: `[3, 2, 1]`
+//! combination 1:
+//!   0) "insert" at src/main.rs:33
+//!   1) "pop+remove+insert+push" at src/main.rs:17
+//! panicked at src/main.rs:22:9:
+//! assertion failed: `(left == right)`
+//!   left: `[3, 2]`,
+//!  right: `[3, 2, 1]`
 //! test example_test ... FAILED
 //! ```
 //!
-//! Note that like all tests in Rust, a section-style test will stop on the
-//! first failure. This means you will only be able to see the first combination
-//! that failed instead of being able to see all failed combinations. The above
-//! example would have also failed for the combination `insert,
-//! pop+remove+insert+push` if the other combination hadn't failed first. This
-//! is because Rust's built-in test runner has no API for adding new tests at
-//! runtime.
+//! Unlike most Rust tests, a section-style test doesn't stop at the first
+//! failing combination. Each combination runs inside `catch_unwind`, so a
+//! panic in one combination doesn't prevent the rest from being explored.
+//! Once every combination has been run, all of the failing combinations are
+//! reported together and the test fails. The above example would report both
+//! `push, pop+remove+insert+push` and `insert, pop+remove+insert+push` as
+//! failing if `v.push(one);` were commented out, instead of only the first
+//! one encountered.
+//!
+//! Setting the `SECTION_TESTING_PERSIST` environment variable to a directory
+//! makes failing section combinations persist to a sidecar file in that
+//! directory (one file per test function, named after the test). The next
+//! time the test runs, those combinations are replayed first, so you get
+//! fast, deterministic iteration on a known-bad combination instead of
+//! waiting for the whole section tree to be walked again.
+//!
+//! Setting the `SECTION_TESTING_FILTER` environment variable to a
+//! `/`-separated path such as `"push/pop+remove+insert+push"` restricts a
+//! run to combinations whose section names match that path, which is handy
+//! for focusing on one branch of a large section tree while debugging it.
+//! Ancestors of a matching combination still run too, since that's the only
+//! way their children get discovered in the first place. Combinations
+//! skipped this way are counted and reported once the run is over.
+//!
+//! Setting the `SECTION_TESTING_REPORT` environment variable to a directory
+//! makes the full explored tree get written as JSON to a sidecar file in
+//! that directory (one file per test function, named after the test), in
+//! addition to the usual stderr summary. Each entry records a combination's
+//! section names, source locations, and whether it passed, failed, or was
+//! skipped by a filter, which is useful for CI tooling or editors that want
+//! to visualize what a section test actually covered.
+//!
+//! Setting the `SECTION_TESTING_PARALLEL` environment variable runs every
+//! combination on a worker thread pool instead of one at a time, pulling
+//! combinations to run from a single queue shared across workers. Running a
+//! combination is also how its children get discovered, so a worker that
+//! finds new sections pushes them onto the same shared queue for whichever
+//! worker is free next, instead of discovering the whole tree up front and
+//! running it all again afterward.
+//!
+//! `section_should_panic!("name")` marks a section whose body is expected to
+//! panic, mirroring `#[should_panic]`. A combination where that section is
+//! active is reported as passing if it panics and failing if it doesn't,
+//! instead of the usual way around.
 
 use std::mem::swap;
+use std::panic;
+use std::fs;
 use std::fmt::Write;
+use std::io::Write as _;
 use std::cell::RefCell;
+use std::path::{Path, PathBuf};
 use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
 
 thread_local! {
   static CURRENT_RUNNER: RefCell<Runner> = RefCell::new(Runner::new());
+  static PANIC_MESSAGES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
 }
 
 #[derive(PartialEq, Eq, Hash, Clone, Copy)]
@@ -109,13 +163,28 @@ struct Section {
 struct Entry {
   should_enter: bool,
   index: usize,
+  expect_panic: bool,
 }
 
 struct Runner {
   is_running: bool,
   queue: VecDeque<HashMap<Section, Entry>>,
   current: HashMap<Section, Entry>,
-  new: Vec<Section>,
+  new: Vec<(Section, bool)>,
+  findings: Vec<Finding>,
+  persist_path: Option<PathBuf>,
+  filter: Vec<String>,
+  report_path: Option<PathBuf>,
+  // Set while running one combination on a `SECTION_TESTING_PARALLEL` worker
+  // thread, in which case newly-discovered sections and this combination's
+  // outcome are routed through the queue and results shared across workers
+  // instead of `queue`/`findings` above, which a worker's runner never uses.
+  shared: Option<Arc<SharedQueue>>,
+  // This combination's position in the order workers picked combinations up
+  // in, assigned by `SharedQueue::sequencer` when it was dequeued, so the
+  // final report can be put back in dequeue order rather than the order
+  // workers finished in. Only ever set alongside `shared`.
+  sequence: Option<usize>,
 }
 
 impl Runner {
@@ -125,10 +194,72 @@ impl Runner {
       queue: vec![HashMap::new()].into(),
       current: HashMap::new(),
       new: vec![],
+      findings: vec![],
+      persist_path: None,
+      filter: vec![],
+      report_path: None,
+      shared: None,
+      sequence: None,
+    }
+  }
+}
+
+/// The work queue and results shared by every worker thread in a
+/// `SECTION_TESTING_PARALLEL` run, so that a combination discovered by one
+/// worker can be picked up and run by whichever worker is free next instead
+/// of being re-run by the thread that found it.
+struct SharedQueue {
+  queue: Mutex<VecDeque<HashMap<Section, Entry>>>,
+  // Combinations that are queued or still being run. Reaching zero with the
+  // queue empty is how workers know the whole tree has been explored.
+  pending: AtomicUsize,
+  sequencer: AtomicUsize,
+  results: Mutex<Vec<(usize, Finding)>>,
+  persist_path: Option<PathBuf>,
+  persist_lock: Mutex<()>,
+  filter: Vec<String>,
+}
+
+/// The outcome of a single explored section combination.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+  Passed,
+  Failed,
+  Skipped,
+}
+
+impl Outcome {
+  fn as_str(self) -> &'static str {
+    match self {
+      Outcome::Passed => "passed",
+      Outcome::Failed => "failed",
+      Outcome::Skipped => "skipped",
     }
   }
 }
 
+/// One explored section combination and how it turned out, used to build
+/// the end-of-run report. `message` is the panic message captured for this
+/// combination, if any, recorded at the moment the combination finishes so
+/// it can't drift out of sync with combinations that fail without panicking
+/// (a `section_should_panic!` section that didn't panic, or a skip).
+struct Finding {
+  path: Vec<Section>,
+  outcome: Outcome,
+  message: Option<String>,
+}
+
+/// The sorted path of currently-active sections in `entries`, outermost
+/// first, exactly like the path a failing combination is reported with.
+fn path_of(entries: &HashMap<Section, Entry>) -> Vec<Section> {
+  let mut entered: Vec<_> = entries.iter()
+    .map(|(k, v)| (*k, *v))
+    .filter(|(_, v)| v.should_enter)
+    .collect();
+  entered.sort_unstable_by(|a, b| a.1.index.cmp(&b.1.index));
+  entered.into_iter().map(|(section, _)| section).collect()
+}
+
 pub struct DropHandler {
   pub is_top_level: bool,
   pub was_success: bool,
@@ -141,56 +272,330 @@ impl Drop for DropHandler {
     }
 
     CURRENT_RUNNER.with(|r| {
-      r.borrow_mut().is_running = false;
-
-      // Did the test complete successfully?
-      if self.was_success {
-        let mut r = r.borrow_mut();
-        let mut new = vec![];
-        swap(&mut r.new, &mut new);
-
-        // If so, add newly-discovered sections to the queue
-        for section in &new {
-          let mut path = r.current.clone();
-          let count = r.current.values().filter(|x| x.should_enter).count();
-          for s in &new {
-            path.insert(*s, Entry {
-              should_enter: s == section,
-              index: count,
-            });
-          }
-          r.queue.push_back(path);
+      let mut r = r.borrow_mut();
+      r.is_running = false;
+
+      // Add any newly-discovered sections to the queue. This happens
+      // regardless of whether this iteration passed or failed, since
+      // sections entered before a panic were still genuinely discovered
+      // and deserve their own run later. In parallel mode these go onto the
+      // queue shared by every worker instead of this thread's own, so
+      // whichever worker is free next picks them up.
+      let mut new = vec![];
+      swap(&mut r.new, &mut new);
+      let mut children = vec![];
+      for (section, _expect_panic) in &new {
+        let mut path = r.current.clone();
+        let count = r.current.values().filter(|x| x.should_enter).count();
+        for (s, e) in &new {
+          path.insert(*s, Entry {
+            should_enter: s == section,
+            index: count,
+            expect_panic: *e,
+          });
         }
+        children.push(path);
+      }
+      if let Some(shared) = &r.shared {
+        if !children.is_empty() {
+          shared.pending.fetch_add(children.len(), Ordering::SeqCst);
+          shared.queue.lock().unwrap().extend(children);
+        }
+      } else {
+        r.queue.extend(children);
       }
 
-      // Is the test in the middle of unwinding due to a panic?
-      else {
-        let mut current: Vec<_> = r.borrow().current.iter()
-          .map(|(k, v)| (*k, *v))
-          .filter(|(_, v)| v.should_enter)
-          .collect();
-        current.sort_unstable_by(|a, b| a.1.index.cmp(&b.1.index));
-
-        // Write out the failure as a single buffer to avoid it interleaving with other output
-        if !current.is_empty() {
-          let mut buffer = "---- the failure was inside these sections ----\n".to_owned();
-          for (i, (section, _)) in current.iter().enumerate() {
-            writeln!(&mut buffer, "{: >3}) {:?} at {}:{}",
-              i, section.name, section.file, section.line).unwrap();
+      // The very first iteration has no sections active at all; when it
+      // passes it has only discovered the top-level ones above and isn't
+      // reported as a finding. A panic there (e.g. in a test with no sections)
+      // is still reported as a failure with an empty path.
+      let path = path_of(&r.current);
+      if path.is_empty() && self.was_success {
+        if let Some(shared) = &r.shared {
+          shared.pending.fetch_sub(1, Ordering::SeqCst);
+        }
+        return;
+      }
+
+      // A "section_should_panic!" section inverts the usual pass/fail
+      // decision for any combination where it's active: not panicking is
+      // what's actually wrong. Recomputed from the active entries rather
+      // than tracked as the combination runs, since a later plain `section!`
+      // nested inside an active should-panic section must not clear it.
+      let expect_panic = r.current.values().any(|e| e.should_enter && e.expect_panic);
+      let was_success = if expect_panic { !self.was_success } else { self.was_success };
+
+      // Remember the outcome of this combination so it can be reported once
+      // the queue has drained, regardless of whether it passed or failed
+      let outcome = if was_success { Outcome::Passed } else { Outcome::Failed };
+
+      // A panic (whether or not it ends up counting as a failure once
+      // `expect_panic` is accounted for) only ever happens on this thread
+      // during the call above, so this is the one and only message for it.
+      // Captured here and attached to the `Finding` itself rather than kept
+      // in a side array, since a failure that isn't an actual panic (a
+      // `section_should_panic!` section that didn't panic) leaves no message
+      // behind and would otherwise throw off a position-based pairing.
+      let message = if !self.was_success {
+        PANIC_MESSAGES.with(|m| m.borrow_mut().pop())
+      } else {
+        None
+      };
+
+      // Persist the failing path so it can be replayed first next time
+      if !was_success {
+        if let Some(persist_path) = r.persist_path.clone() {
+          if let Some(shared) = &r.shared {
+            let _guard = shared.persist_lock.lock().unwrap();
+            append_persisted_path(&persist_path, &path);
+          } else {
+            append_persisted_path(&persist_path, &path);
           }
-          eprint!("{}", buffer);
         }
       }
+
+      if let Some(shared) = r.shared.clone() {
+        let sequence = r.sequence.take().unwrap_or(0);
+        shared.results.lock().unwrap().push((sequence, Finding {path, outcome, message}));
+        shared.pending.fetch_sub(1, Ordering::SeqCst);
+      } else {
+        r.findings.push(Finding {path, outcome, message});
+      }
     });
   }
 }
 
-pub fn enable_sections_start() -> bool {
+/// Installs a panic hook that buffers panic messages instead of printing
+/// them immediately, so that messages from different combinations don't
+/// interleave with each other. Returns the previously-installed hook so it
+/// can be restored by `finish_sections` once the run is over.
+pub fn install_panic_hook() -> Box<dyn Fn(&panic::PanicHookInfo) + Sync + Send> {
+  PANIC_MESSAGES.with(|m| m.borrow_mut().clear());
+  let previous = panic::take_hook();
+  panic::set_hook(Box::new(|info| {
+    PANIC_MESSAGES.with(|m| m.borrow_mut().push(info.to_string()));
+  }));
+  previous
+}
+
+/// Called by `finish_sections` with every combination explored during a run
+/// once the queue has drained. `HumanReporter` is the original stderr
+/// summary; `JsonReporter` additionally writes the full explored tree to a
+/// file for tooling to consume. Mirrors the reporter abstraction `tester`
+/// and `libtest` use for their console output.
+trait Reporter {
+  fn report(&self, findings: &[Finding]);
+}
+
+struct HumanReporter;
+
+impl Reporter for HumanReporter {
+  fn report(&self, findings: &[Finding]) {
+    let skipped = findings.iter().filter(|f| f.outcome == Outcome::Skipped).count();
+    if skipped > 0 {
+      eprintln!("---- {} section combination(s) skipped by \"SECTION_TESTING_FILTER\" ----", skipped);
+    }
+
+    let failures: Vec<&Finding> = findings.iter().filter(|f| f.outcome == Outcome::Failed).collect();
+    if failures.is_empty() {
+      return;
+    }
+
+    let mut buffer = format!("---- {} section combination(s) failed ----\n", failures.len());
+    for (i, finding) in failures.iter().enumerate() {
+      writeln!(&mut buffer, "combination {}:", i).unwrap();
+      for (j, section) in finding.path.iter().enumerate() {
+        writeln!(&mut buffer, "{: >3}) {:?} at {}:{}", j, section.name, section.file, section.line).unwrap();
+      }
+      if let Some(message) = &finding.message {
+        writeln!(&mut buffer, "{}", message).unwrap();
+      }
+    }
+    eprint!("{}", buffer);
+  }
+}
+
+/// Writes the full explored tree as JSON to `path`, one object per
+/// combination with its section path and pass/fail/skip status.
+struct JsonReporter {
+  path: PathBuf,
+}
+
+impl Reporter for JsonReporter {
+  fn report(&self, findings: &[Finding]) {
+    let mut json = String::from("[");
+    for (i, finding) in findings.iter().enumerate() {
+      if i > 0 {
+        json.push(',');
+      }
+      write!(&mut json, "{{\"status\":{},\"path\":[", json_string(finding.outcome.as_str())).unwrap();
+      for (j, section) in finding.path.iter().enumerate() {
+        if j > 0 {
+          json.push(',');
+        }
+        write!(&mut json, "{{\"name\":{},\"file\":{},\"line\":{}}}", json_string(section.name), json_string(section.file), section.line).unwrap();
+      }
+      json.push_str("]}");
+    }
+    json.push(']');
+
+    if let Some(parent) = self.path.parent() {
+      let _ = fs::create_dir_all(parent);
+    }
+    let _ = fs::write(&self.path, json);
+  }
+}
+
+/// Escapes `s` as a JSON string literal, including the surrounding quotes.
+fn json_string(s: &str) -> String {
+  let mut out = String::with_capacity(s.len() + 2);
+  out.push('"');
+  for c in s.chars() {
+    match c {
+      '"' => out.push_str("\\\""),
+      '\\' => out.push_str("\\\\"),
+      '\n' => out.push_str("\\n"),
+      c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+      _ => out.push(c),
+    }
+  }
+  out.push('"');
+  out
+}
+
+/// Restores the panic hook installed by `install_panic_hook`, then reports
+/// every combination collected during the run and panics if any failed, so
+/// the `#[test]` is still reported as failed.
+pub fn finish_sections(previous_hook: Box<dyn Fn(&panic::PanicHookInfo) + Sync + Send>) {
+  panic::set_hook(previous_hook);
+
+  let (findings, report_path) = CURRENT_RUNNER.with(|r| {
+    let mut r = r.borrow_mut();
+    (std::mem::take(&mut r.findings), r.report_path.clone())
+  });
+
+  let mut reporters: Vec<Box<dyn Reporter>> = vec![Box::new(HumanReporter)];
+  if let Some(path) = report_path {
+    reporters.push(Box::new(JsonReporter {path}));
+  }
+  for reporter in &reporters {
+    reporter.report(&findings);
+  }
+
+  let failed = findings.iter().filter(|f| f.outcome == Outcome::Failed).count();
+  if failed > 0 {
+    panic!("{} section combination(s) failed, see above for details", failed);
+  }
+}
+
+/// The sidecar file a failing combination for `name` is persisted to, or
+/// `None` if `SECTION_TESTING_PERSIST` isn't set.
+fn persist_path_for(name: &str) -> Option<PathBuf> {
+  let dir = std::env::var_os("SECTION_TESTING_PERSIST")?;
+  Some(Path::new(&dir).join(format!("{}.sections", name)))
+}
+
+/// Sections within a path are separated by a record separator and a
+/// section's fields are separated by a unit separator, so that section
+/// names are free to contain any other character except a line break, since
+/// each path is stored on a line of its own.
+const PERSISTED_PATH_SEPARATOR: char = '\u{1e}';
+const PERSISTED_SECTION_SEPARATOR: char = '\u{1f}';
+
+fn append_persisted_path(path: &Path, sections: &[Section]) {
+  if let Some(parent) = path.parent() {
+    let _ = fs::create_dir_all(parent);
+  }
+  let line: String = sections.iter()
+    .map(|s| format!("{}{}{}{}{}", s.name, PERSISTED_SECTION_SEPARATOR, s.file, PERSISTED_SECTION_SEPARATOR, s.line))
+    .collect::<Vec<_>>()
+    .join(&PERSISTED_PATH_SEPARATOR.to_string());
+
+  // Don't grow the sidecar file with a duplicate line every time the same
+  // combination fails again; it's meant to be a stable, reviewable corpus.
+  if let Ok(contents) = fs::read_to_string(path) {
+    if contents.lines().any(|existing| existing == line) {
+      return;
+    }
+  }
+
+  if let Ok(mut file) = fs::OpenOptions::new().create(true).append(true).open(path) {
+    let _ = writeln!(file, "{}", line);
+  }
+}
+
+fn load_persisted_paths(path: &Path) -> Vec<Vec<Section>> {
+  let contents = match fs::read_to_string(path) {
+    Ok(contents) => contents,
+    Err(_) => return vec![],
+  };
+
+  contents.lines()
+    .filter(|line| !line.is_empty())
+    .map(|line| {
+      line.split(PERSISTED_PATH_SEPARATOR).map(|field| {
+        let mut parts = field.splitn(3, PERSISTED_SECTION_SEPARATOR);
+        let name = parts.next().unwrap_or("");
+        let file = parts.next().unwrap_or("");
+        let line = parts.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+        Section {
+          name: Box::leak(name.to_owned().into_boxed_str()),
+          file: Box::leak(file.to_owned().into_boxed_str()),
+          line,
+        }
+      }).collect()
+    })
+    .collect()
+}
+
+/// The `/`-separated path read from `SECTION_TESTING_FILTER`, split into its
+/// section-name components, or an empty `Vec` if the variable isn't set (in
+/// which case every combination matches).
+fn filter_components() -> Vec<String> {
+  match std::env::var("SECTION_TESTING_FILTER") {
+    Ok(pattern) => pattern.split('/').map(|s| s.to_owned()).collect(),
+    Err(_) => vec![],
+  }
+}
+
+/// Whether `path` should run given `filter`. Compares section names
+/// position-by-position up to the shorter of the two, so an empty filter (or
+/// a path that hasn't diverged from it yet) always matches, letting
+/// ancestors of a matching combination run and discover their children.
+fn matches_filter(path: &HashMap<Section, Entry>, filter: &[String]) -> bool {
+  path_of(path).iter().zip(filter.iter()).all(|(section, name)| section.name == name)
+}
+
+/// The sidecar file the explored tree for `name` is reported to as JSON, or
+/// `None` if `SECTION_TESTING_REPORT` isn't set.
+fn report_path_for(name: &str) -> Option<PathBuf> {
+  let dir = std::env::var_os("SECTION_TESTING_REPORT")?;
+  Some(Path::new(&dir).join(format!("{}.json", name)))
+}
+
+pub fn enable_sections_start(name: &str) -> bool {
   CURRENT_RUNNER.with(|r| {
     if r.borrow().is_running {
       false
     } else {
-      r.replace(Runner::new());
+      let mut runner = Runner::new();
+      runner.persist_path = persist_path_for(name);
+      runner.filter = filter_components();
+      runner.report_path = report_path_for(name);
+
+      // Seed the queue with any persisted failing paths before the fresh
+      // enumeration below discovers new ones, so they run first
+      if let Some(persist_path) = &runner.persist_path {
+        let mut seeded: VecDeque<_> = load_persisted_paths(persist_path).into_iter()
+          .map(|path| path.into_iter().enumerate()
+            .map(|(index, section)| (section, Entry {should_enter: true, index, expect_panic: false}))
+            .collect())
+          .collect();
+        seeded.extend(runner.queue.drain(..));
+        runner.queue = seeded;
+      }
+
+      r.replace(runner);
       true
     }
   })
@@ -199,13 +604,18 @@ pub fn enable_sections_start() -> bool {
 pub fn enable_sections_step() -> bool {
   CURRENT_RUNNER.with(|r| {
     let mut r = r.borrow_mut();
-    if let Some(current) = r.queue.pop_front() {
-      r.current = current;
-      r.new.clear();
-      r.is_running = true;
-      true
-    } else {
-      false
+    loop {
+      let current = match r.queue.pop_front() {
+        Some(current) => current,
+        None => return false,
+      };
+      if matches_filter(&current, &r.filter) {
+        r.current = current;
+        r.new.clear();
+        r.is_running = true;
+        return true;
+      }
+      r.findings.push(Finding {path: path_of(&current), outcome: Outcome::Skipped, message: None});
     }
   })
 }
@@ -213,11 +623,42 @@ pub fn enable_sections_step() -> bool {
 pub fn enter_section(name: &'static str, file: &'static str, line: u32) -> bool {
   CURRENT_RUNNER.with(|r| {
     let section = Section {name, file, line};
-    let should_enter = r.borrow().current.get(&section).map(|x| x.should_enter);
-    should_enter.unwrap_or_else(|| {
-      r.borrow_mut().new.push(section);
-      false
-    })
+    let mut r = r.borrow_mut();
+    match r.current.get(&section).map(|x| x.should_enter) {
+      Some(should_enter) => should_enter,
+      None => {
+        r.new.push((section, false));
+        false
+      }
+    }
+  })
+}
+
+/// Like `enter_section`, but for a section whose body is expected to panic.
+/// If this section ends up active, its `Entry::expect_panic` is set so that
+/// `DropHandler` treats a panic inside it as success and not panicking as
+/// failure, mirroring libtest's `#[should_panic]`. This is read back off
+/// every active entry in `Runner::current` rather than tracked as a single
+/// flag, so a plain `section!` entered deeper inside an active
+/// `section_should_panic!` doesn't clear it.
+pub fn enter_section_should_panic(name: &'static str, file: &'static str, line: u32) -> bool {
+  CURRENT_RUNNER.with(|r| {
+    let section = Section {name, file, line};
+    let mut r = r.borrow_mut();
+    match r.current.get(&section).map(|x| x.should_enter) {
+      Some(should_enter) => {
+        if should_enter {
+          if let Some(entry) = r.current.get_mut(&section) {
+            entry.expect_panic = true;
+          }
+        }
+        should_enter
+      }
+      None => {
+        r.new.push((section, true));
+        false
+      }
+    }
   })
 }
 
@@ -225,6 +666,145 @@ pub fn is_running() -> bool {
   CURRENT_RUNNER.with(|r| r.borrow().is_running)
 }
 
+/// Whether `SECTION_TESTING_PARALLEL` is set, i.e. whether `enable_sections!`
+/// should run `run_parallel_sections` instead of its usual sequential loop.
+pub fn is_parallel() -> bool {
+  std::env::var_os("SECTION_TESTING_PARALLEL").is_some()
+}
+
+/// Runs every combination of a section-testing function across a pool of
+/// worker threads that all pull from one shared queue, so a combination a
+/// worker just discovered can be picked up by whichever worker is free next
+/// instead of being re-run later by the thread that found it. Each
+/// combination therefore still only runs once overall, the same as the
+/// sequential engine, just spread across threads instead of one at a time.
+pub fn run_parallel_sections<F>(name: &str, body: F)
+where
+  F: Fn() + Send + Sync + 'static,
+{
+  // A nested section-testing function called from inside one that's already
+  // running just runs its body once on the calling (worker) thread, exactly
+  // like the non-top-level case in the sequential engine's loop.
+  if is_running() {
+    let mut scope = DropHandler {is_top_level: false, was_success: false};
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(body));
+    scope.was_success = result.is_ok();
+    drop(scope);
+    if let Err(payload) = result {
+      panic::resume_unwind(payload);
+    }
+    return;
+  }
+
+  let persist_path = persist_path_for(name);
+  let report_path = report_path_for(name);
+
+  // Seed the queue with any persisted failing paths, plus the empty root
+  // combination that discovers the top-level sections, exactly like the
+  // sequential engine's starting queue in `enable_sections_start`.
+  let mut queue: VecDeque<HashMap<Section, Entry>> = persist_path.as_deref()
+    .map(load_persisted_paths)
+    .unwrap_or_default()
+    .into_iter()
+    .map(|path| path.into_iter().enumerate()
+      .map(|(index, section)| (section, Entry {should_enter: true, index, expect_panic: false}))
+      .collect())
+    .collect();
+  queue.push_back(HashMap::new());
+
+  let shared = Arc::new(SharedQueue {
+    pending: AtomicUsize::new(queue.len()),
+    sequencer: AtomicUsize::new(0),
+    queue: Mutex::new(queue),
+    results: Mutex::new(vec![]),
+    persist_path: persist_path.clone(),
+    persist_lock: Mutex::new(()),
+    filter: filter_components(),
+  });
+
+  CURRENT_RUNNER.with(|r| {
+    let mut runner = Runner::new();
+    runner.is_running = true;
+    runner.persist_path = persist_path;
+    runner.report_path = report_path;
+    r.replace(runner);
+  });
+  let previous_hook = install_panic_hook();
+
+  let body: Arc<dyn Fn() + Send + Sync> = Arc::new(body);
+  let worker_count = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+  let handles: Vec<_> = (0..worker_count).map(|_| {
+    let shared = Arc::clone(&shared);
+    let body = Arc::clone(&body);
+    thread::spawn(move || run_parallel_worker(shared, body))
+  }).collect();
+  for handle in handles {
+    let _ = handle.join();
+  }
+
+  let mut results = match Arc::try_unwrap(shared) {
+    Ok(shared) => shared.results.into_inner().unwrap(),
+    Err(shared) => shared.results.lock().unwrap().drain(..).collect(),
+  };
+  // Put the results back in the order combinations were dequeued, not
+  // whatever order the workers that ran them happened to finish in.
+  results.sort_by_key(|(sequence, ..)| *sequence);
+
+  CURRENT_RUNNER.with(|r| {
+    let mut r = r.borrow_mut();
+    r.is_running = false;
+    r.findings = results.into_iter().map(|(_, finding)| finding).collect();
+  });
+
+  finish_sections(previous_hook);
+}
+
+/// One worker thread's share of a `SECTION_TESTING_PARALLEL` run: pop
+/// combinations off `shared`'s queue and run them until it's empty and
+/// nothing is left in flight, at which point the whole tree has been
+/// explored and every worker converges on returning.
+fn run_parallel_worker(shared: Arc<SharedQueue>, body: Arc<dyn Fn() + Send + Sync>) {
+  loop {
+    let current = shared.queue.lock().unwrap().pop_front();
+    let current = match current {
+      Some(current) => current,
+      // Nothing queued right now, but another worker might still discover
+      // more work before finishing its own combination, so only stop once
+      // nothing is queued or in flight anywhere.
+      None if shared.pending.load(Ordering::SeqCst) == 0 => return,
+      None => {
+        thread::yield_now();
+        continue;
+      }
+    };
+
+    if !matches_filter(&current, &shared.filter) {
+      let sequence = shared.sequencer.fetch_add(1, Ordering::SeqCst);
+      let finding = Finding {path: path_of(&current), outcome: Outcome::Skipped, message: None};
+      shared.results.lock().unwrap().push((sequence, finding));
+      shared.pending.fetch_sub(1, Ordering::SeqCst);
+      continue;
+    }
+
+    let sequence = shared.sequencer.fetch_add(1, Ordering::SeqCst);
+    CURRENT_RUNNER.with(|r| {
+      let mut runner = Runner::new();
+      runner.is_running = true;
+      runner.persist_path = shared.persist_path.clone();
+      runner.shared = Some(Arc::clone(&shared));
+      runner.sequence = Some(sequence);
+      runner.current = current;
+      r.replace(runner);
+    });
+    PANIC_MESSAGES.with(|m| m.borrow_mut().clear());
+
+    let mut scope = DropHandler {is_top_level: true, was_success: false};
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(body.as_ref()));
+    scope.was_success = result.is_ok();
+    drop(scope);
+  }
+}
+
 #[macro_export]
 macro_rules! enable_sections {
   (
@@ -238,23 +818,49 @@ macro_rules! enable_sections {
     $(
       $(#[$($attrs)*])*
       fn $name() {
-        let is_top_level = $crate::enable_sections_start();
+        // "SECTION_TESTING_PARALLEL" runs every combination across a pool of
+        // worker threads sharing one queue instead of the sequential loop
+        // below; see "run_parallel_sections" for that engine.
+        if $crate::is_parallel() {
+          $crate::run_parallel_sections(stringify!($name), || {
+            $($arg)*
+          });
+          return;
+        }
+
+        let is_top_level = $crate::enable_sections_start(stringify!($name));
+        let previous_hook = if is_top_level { Some($crate::install_panic_hook()) } else { None };
+
         loop {
           // Stop this run when the queue is empty
           if is_top_level && !$crate::enable_sections_step() {
             break;
           }
 
-          // Run the function body
-          let mut scope = $crate::DropHandler {is_top_level, was_success: false};
-          $($arg)*
-          scope.was_success = true;
+          // Run the function body, catching a panic instead of letting it
+          // unwind past this combination so the rest can still be explored
+          let result = {
+            let mut scope = $crate::DropHandler {is_top_level, was_success: false};
+            let result = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| {
+              $($arg)*
+            }));
+            scope.was_success = result.is_ok();
+            result
+          };
 
-          // Only run the function body once if we're not top-level
+          // Only run the function body once if we're not top-level, and let
+          // a panic propagate normally since there's no queue to keep draining
           if !is_top_level {
+            if let Err(payload) = result {
+              ::std::panic::resume_unwind(payload);
+            }
             break;
           }
         }
+
+        if let Some(previous_hook) = previous_hook {
+          $crate::finish_sections(previous_hook);
+        }
       }
     )*
   }
@@ -267,3 +873,11 @@ macro_rules! section {
     $crate::enter_section($name, file!(), line!())
   }}
 }
+
+#[macro_export]
+macro_rules! section_should_panic {
+  ($name:expr) => {{
+    assert!($crate::is_running(), "\"section_should_panic!(...)\" must be called from inside \"enable_sections! { ... }\"");
+    $crate::enter_section_should_panic($name, file!(), line!())
+  }}
+}
@@ -0,0 +1,22 @@
+#[macro_use]
+extern crate section_testing;
+
+// Not a `#[test]` itself so it can be called directly and its panic message
+// inspected, since a combination failing necessarily panics the caller.
+enable_sections! {
+  fn two_failures() {
+    if section!("first") {
+      panic!("first failed");
+    }
+
+    if section!("second") {
+      panic!("second failed");
+    }
+  }
+}
+
+#[test]
+#[should_panic(expected = "2 section combination(s) failed")]
+fn collects_every_failing_combination_instead_of_stopping_at_the_first() {
+  two_failures();
+}
@@ -0,0 +1,23 @@
+#[macro_use]
+extern crate section_testing;
+
+enable_sections! {
+  fn two_branches() {
+    if section!("alpha") {
+      panic!("\"alpha\" should have been skipped by the filter");
+    }
+
+    if section!("beta") {
+      // Matches the filter below, so this one should actually run.
+    }
+  }
+}
+
+#[test]
+fn filter_skips_combinations_that_do_not_match() {
+  std::env::set_var("SECTION_TESTING_FILTER", "beta");
+  let result = std::panic::catch_unwind(two_branches);
+  std::env::remove_var("SECTION_TESTING_FILTER");
+
+  assert!(result.is_ok(), "the \"alpha\" combination should have been skipped instead of running and panicking");
+}
@@ -0,0 +1,36 @@
+#[macro_use]
+extern crate section_testing;
+
+use std::fs;
+
+enable_sections! {
+  fn two_failures_in_parallel() {
+    if section!("first") {
+      panic!("first failed");
+    }
+
+    if section!("second") {
+      panic!("second failed");
+    }
+  }
+}
+
+#[test]
+fn parallel_mode_collects_every_failing_combination_exactly_once() {
+  let dir = std::env::temp_dir().join(format!("section_testing_parallel_test_{}", std::process::id()));
+  let _ = fs::remove_dir_all(&dir);
+  std::env::set_var("SECTION_TESTING_PARALLEL", "1");
+  std::env::set_var("SECTION_TESTING_REPORT", &dir);
+
+  let result = std::panic::catch_unwind(two_failures_in_parallel);
+
+  std::env::remove_var("SECTION_TESTING_PARALLEL");
+  std::env::remove_var("SECTION_TESTING_REPORT");
+
+  assert!(result.is_err(), "both combinations panic, so the whole test should still fail under the worker pool");
+
+  let report = fs::read_to_string(dir.join("two_failures_in_parallel.json")).expect("report file should have been written");
+  fs::remove_dir_all(&dir).unwrap();
+
+  assert_eq!(report.matches("\"status\":\"failed\"").count(), 2, "each combination discovered by a worker must be run, and fail, exactly once instead of being skipped or double-run by the shared queue");
+}
@@ -0,0 +1,68 @@
+#[macro_use]
+extern crate section_testing;
+
+use std::fs;
+
+enable_sections! {
+  fn always_fails() {
+    if section!("broken") {
+      panic!("broken failed");
+    }
+  }
+
+  fn order_matters() {
+    if section!("extra") {
+      // passes
+    }
+
+    if section!("broken") {
+      panic!("broken failed");
+    }
+  }
+}
+
+// Both cases below mutate the process-global "SECTION_TESTING_PERSIST" and
+// "SECTION_TESTING_REPORT" env vars, which would race against each other if
+// split into separate #[test] fns (cargo runs those concurrently by
+// default), so they're kept in one test and run one after the other.
+#[test]
+fn persisted_failures_are_deduplicated_and_replayed() {
+  let dir = std::env::temp_dir().join(format!("section_testing_persist_dedup_test_{}", std::process::id()));
+  let _ = fs::remove_dir_all(&dir);
+  std::env::set_var("SECTION_TESTING_PERSIST", &dir);
+
+  for _ in 0..2 {
+    let _ = std::panic::catch_unwind(always_fails);
+  }
+  std::env::remove_var("SECTION_TESTING_PERSIST");
+
+  let contents = fs::read_to_string(dir.join("always_fails.sections")).expect("sidecar file should have been written");
+  fs::remove_dir_all(&dir).unwrap();
+
+  assert_eq!(contents.lines().count(), 1, "failing the same combination twice shouldn't append a duplicate line");
+
+  let persist_dir = std::env::temp_dir().join(format!("section_testing_persist_replay_test_{}", std::process::id()));
+  let report_dir = std::env::temp_dir().join(format!("section_testing_persist_replay_report_{}", std::process::id()));
+  let _ = fs::remove_dir_all(&persist_dir);
+  let _ = fs::remove_dir_all(&report_dir);
+  std::env::set_var("SECTION_TESTING_PERSIST", &persist_dir);
+
+  // First run discovers "extra" before "broken" (declaration order) and
+  // persists the failing "broken" path.
+  let _ = std::panic::catch_unwind(order_matters);
+
+  // Second run should replay the persisted "broken" combination first, ahead
+  // of "extra", even though fresh discovery would find "extra" first.
+  std::env::set_var("SECTION_TESTING_REPORT", &report_dir);
+  let _ = std::panic::catch_unwind(order_matters);
+  std::env::remove_var("SECTION_TESTING_PERSIST");
+  std::env::remove_var("SECTION_TESTING_REPORT");
+
+  let report = fs::read_to_string(report_dir.join("order_matters.json")).expect("report file should have been written");
+  fs::remove_dir_all(&persist_dir).unwrap();
+  fs::remove_dir_all(&report_dir).unwrap();
+
+  let broken_pos = report.find("\"broken\"").expect("\"broken\" should be in the report");
+  let extra_pos = report.find("\"extra\"").expect("\"extra\" should be in the report");
+  assert!(broken_pos < extra_pos, "the persisted \"broken\" failure should replay before \"extra\" is freshly discovered");
+}
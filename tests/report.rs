@@ -0,0 +1,33 @@
+#[macro_use]
+extern crate section_testing;
+
+use std::fs;
+
+enable_sections! {
+  fn reported_test() {
+    if section!("alpha") {
+      // passes
+    }
+
+    if section!("beta") {
+      panic!("beta failed");
+    }
+  }
+}
+
+#[test]
+fn report_contains_every_real_combination_and_nothing_else() {
+  let dir = std::env::temp_dir().join(format!("section_testing_report_test_{}", std::process::id()));
+  let _ = fs::remove_dir_all(&dir);
+  std::env::set_var("SECTION_TESTING_REPORT", &dir);
+
+  let _ = std::panic::catch_unwind(reported_test);
+  std::env::remove_var("SECTION_TESTING_REPORT");
+
+  let report = fs::read_to_string(dir.join("reported_test.json")).expect("report file should have been written");
+  fs::remove_dir_all(&dir).unwrap();
+
+  assert!(report.contains("\"status\":\"passed\""), "the passing \"alpha\" combination should be in the report");
+  assert!(report.contains("\"status\":\"failed\""), "the failing \"beta\" combination should be in the report");
+  assert!(!report.contains("\"path\":[]"), "the empty-path discovery iteration isn't a real combination and shouldn't be reported");
+}
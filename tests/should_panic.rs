@@ -0,0 +1,45 @@
+#[macro_use]
+extern crate section_testing;
+
+enable_sections! {
+  fn simple_should_panic() {
+    if section_should_panic!("explodes") {
+      panic!("boom");
+    }
+  }
+
+  fn simple_should_panic_but_does_not() {
+    if section_should_panic!("does_not_explode") {
+      // Doesn't panic, so the "section_should_panic!" contract is broken and
+      // this combination should be reported as failing.
+    }
+  }
+
+  fn nested_should_panic() {
+    if section_should_panic!("explodes") {
+      if section!("nested") {
+        panic!("boom");
+      }
+    }
+  }
+}
+
+#[test]
+fn should_panic_section_passes_when_it_panics() {
+  let result = std::panic::catch_unwind(simple_should_panic);
+  assert!(result.is_ok(), "a \"section_should_panic!\" section that panics should be reported as passing");
+}
+
+#[test]
+#[should_panic(expected = "1 section combination(s) failed")]
+fn should_panic_section_fails_when_it_does_not_panic() {
+  simple_should_panic_but_does_not();
+}
+
+#[test]
+#[should_panic(expected = "1 section combination(s) failed")]
+fn plain_section_nested_inside_should_panic_does_not_clear_it() {
+  // "explodes" alone doesn't panic (since it never reaches "nested"), which
+  // is a genuine failure; "explodes,nested" panics as declared and passes.
+  nested_should_panic();
+}